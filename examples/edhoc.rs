@@ -1,9 +1,6 @@
-use oscore::edhoc::{Msg1Receiver, Msg1Sender};
+use oscore::edhoc::{Msg1Receiver, Msg1Sender, OwnOrPeerError};
 
 fn main() {
-    // TODO: An EDHOC error message should be sent to the other party whenever
-    // an operation fails and the protocol is abandoned.
-
     let v_public = [
         0x88, 0x3D, 0x9F, 0x20, 0xAF, 0x73, 0xF7, 0x8E, 0xD2, 0x94, 0x78,
         0xE4, 0x16, 0x51, 0x4B, 0x88, 0x57, 0x19, 0x64, 0x3B, 0x63, 0xC5,
@@ -26,7 +23,9 @@ fn main() {
     let u_c_u = b"Party U";
 
     let msg1_sender = Msg1Sender::new(u_c_u, u_priv);
-    let (mut msg1_bytes, msg2_receiver) = msg1_sender.generate_message_1();
+    let (mut msg1_bytes, msg2_receiver) = msg1_sender
+        .generate_message_1()
+        .expect("generating message_1 failed");
 
     // Party V ----------------------------------------------------------------
     // "Generate" an ECDH key pair (this is static, but MUST be ephemeral)
@@ -40,24 +39,51 @@ fn main() {
     let v_c_v = b"Party V";
 
     let msg1_receiver = Msg1Receiver::new(v_c_v, v_priv);
-    let msg2_sender = msg1_receiver.handle_message_1(&mut msg1_bytes);
-    let (mut msg2_bytes, msg3_receiver) = msg2_sender.generate_message_2();
+    let msg2_sender = match msg1_receiver.handle_message_1(&mut msg1_bytes) {
+        Ok(msg2_sender) => msg2_sender,
+        // `Own` carries the EDHOC error message for the other party and
+        // `Peer` the diagnostic the other party sent; this example only
+        // reports either and stops.
+        Err(OwnOrPeerError::Own(_error_message)) => panic!("message_1 rejected"),
+        Err(OwnOrPeerError::Peer(diagnostic)) => panic!("{}", diagnostic),
+    };
+    let (mut msg2_bytes, msg3_receiver) = msg2_sender
+        .generate_message_2()
+        .expect("generating message_2 failed");
+
+    // Each party signs with the Ed25519 keypair expanded from its ECDH
+    // private key, so the other party verifies with its public half.
+    let v_public = ed25519_dalek::PublicKey::from(
+        &ed25519_dalek::SecretKey::from_bytes(&v_priv).unwrap(),
+    )
+    .to_bytes();
+    let u_public = ed25519_dalek::PublicKey::from(
+        &ed25519_dalek::SecretKey::from_bytes(&u_priv).unwrap(),
+    )
+    .to_bytes();
 
     // Party U ----------------------------------------------------------------
     let msg3_sender =
-        msg2_receiver.handle_message_2(&mut msg2_bytes, v_public);
-    let (mut msg3_bytes, u_master_secret, u_master_salt) =
-        msg3_sender.generate_message_3();
+        match msg2_receiver.handle_message_2(&mut msg2_bytes, v_public) {
+            Ok(msg3_sender) => msg3_sender,
+            Err(OwnOrPeerError::Own(_error_message)) => {
+                panic!("message_2 rejected")
+            }
+            Err(OwnOrPeerError::Peer(diagnostic)) => panic!("{}", diagnostic),
+        };
+    let (mut msg3_bytes, u_master_secret, u_master_salt) = msg3_sender
+        .generate_message_3()
+        .expect("generating message_3 failed");
 
     // Party V ----------------------------------------------------------------
     let (v_master_secret, v_master_salt) =
-        msg3_receiver.handle_message_3(&mut msg3_bytes, u_public);
-
-    // Party U ----------------------------------------------------------------
-    // It's possible that Party V failed verification of message_3, in which
-    // case it sends an EDHOC error message.
-    // Technically, Party U would have to be ready to receive this message and
-    // invalidate any protocol state.
+        match msg3_receiver.handle_message_3(&mut msg3_bytes, u_public) {
+            Ok(context) => context,
+            Err(OwnOrPeerError::Own(_error_message)) => {
+                panic!("message_3 rejected")
+            }
+            Err(OwnOrPeerError::Peer(diagnostic)) => panic!("{}", diagnostic),
+        };
 
     // Verification -----------------------------------------------------------
     assert_eq!(u_master_secret, v_master_secret);
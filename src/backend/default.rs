@@ -0,0 +1,158 @@
+//! The default, pure-Rust [`CryptoBackend`](super::CryptoBackend)
+//! implementation.
+//!
+//! This backend is what this crate used unconditionally before the
+//! `CryptoBackend` trait was introduced: Ed25519/ECDSA-P256 for signing,
+//! X25519 for key agreement, HKDF-SHA256 for key derivation and AES-CCM for
+//! the AEAD step.
+
+use alloc::vec::Vec;
+use ecdsa::signature::{Signer, Verifier};
+use ed25519_dalek::{Keypair, Signature};
+use hkdf::Hkdf;
+use p256::ecdsa::{Signature as EcdsaSignature, SigningKey, VerifyingKey};
+use sha2::{Digest, Sha256, Sha512};
+
+use super::CryptoBackend;
+use crate::cose::SignatureAlg;
+use crate::{Error, Result};
+
+/// The default software-only crypto backend.
+///
+/// Used unless another backend is selected; suitable for hosts where
+/// dedicating a secure element or PSA Crypto implementation isn't an
+/// option.
+#[derive(Default)]
+pub struct DefaultBackend;
+
+impl CryptoBackend for DefaultBackend {
+    fn sign(
+        &self,
+        alg: SignatureAlg,
+        keypair_bytes: &[u8],
+        bytes: &[u8],
+    ) -> Result<Vec<u8>> {
+        match alg {
+            SignatureAlg::EdDsa => {
+                let keypair = Keypair::from_bytes(keypair_bytes)?;
+                let signature = keypair.sign::<Sha512>(bytes);
+
+                Ok(signature.to_bytes().to_vec())
+            }
+            SignatureAlg::Es256 => {
+                let signing_key = SigningKey::from_bytes(keypair_bytes)
+                    .map_err(|_| Error::Crypto)?;
+                let signature: EcdsaSignature = signing_key.sign(bytes);
+
+                // COSE mandates the fixed-length raw `r || s`
+                // representation, not DER.
+                Ok(signature.as_ref().to_vec())
+            }
+        }
+    }
+
+    fn verify(
+        &self,
+        alg: SignatureAlg,
+        public_key: &[u8],
+        bytes: &[u8],
+        signature: &[u8],
+    ) -> Result<()> {
+        match alg {
+            SignatureAlg::EdDsa => {
+                let public_key =
+                    ed25519_dalek::PublicKey::from_bytes(public_key)?;
+                let signature = Signature::from_bytes(signature)?;
+
+                Ok(public_key.verify::<Sha512>(bytes, &signature)?)
+            }
+            SignatureAlg::Es256 => {
+                // `Signature::from_bytes` rejects a buffer whose `r` or `s`
+                // half is zero or not a valid scalar (i.e. >= curve order).
+                let signature = EcdsaSignature::from_bytes(signature)
+                    .map_err(|_| Error::Crypto)?;
+                let verifying_key = VerifyingKey::from_sec1_bytes(public_key)
+                    .map_err(|_| Error::Crypto)?;
+
+                verifying_key
+                    .verify(bytes, &signature)
+                    .map_err(|_| Error::Crypto)
+            }
+        }
+    }
+
+    fn ecdh_public(&self, private_key: &[u8; 32]) -> Result<[u8; 32]> {
+        let secret = x25519_dalek::StaticSecret::from(*private_key);
+
+        Ok(x25519_dalek::PublicKey::from(&secret).to_bytes())
+    }
+
+    fn ecdh(
+        &self,
+        private_key: &[u8; 32],
+        public_key: &[u8; 32],
+    ) -> Result<[u8; 32]> {
+        let secret = x25519_dalek::StaticSecret::from(*private_key);
+        let public = x25519_dalek::PublicKey::from(*public_key);
+
+        Ok(secret.diffie_hellman(&public).to_bytes())
+    }
+
+    fn hkdf_expand(
+        &self,
+        prk: &[u8],
+        info: &[u8],
+        length: usize,
+    ) -> Result<Vec<u8>> {
+        let hkdf = Hkdf::<Sha256>::from_prk(prk).map_err(|_| Error::Crypto)?;
+        let mut okm = alloc::vec![0; length];
+        hkdf.expand(info, &mut okm).map_err(|_| Error::Crypto)?;
+
+        Ok(okm)
+    }
+
+    fn aead_seal(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>> {
+        use aes_ccm::aead::{generic_array::GenericArray, Aead, NewAead, Payload};
+        use aes_ccm::Aes128Ccm;
+
+        let cipher = Aes128Ccm::new(GenericArray::from_slice(key));
+        cipher
+            .encrypt(
+                GenericArray::from_slice(nonce),
+                Payload { msg: plaintext, aad },
+            )
+            .map_err(|_| Error::Crypto)
+    }
+
+    fn aead_open(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>> {
+        use aes_ccm::aead::{generic_array::GenericArray, Aead, NewAead, Payload};
+        use aes_ccm::Aes128Ccm;
+
+        let cipher = Aes128Ccm::new(GenericArray::from_slice(key));
+        cipher
+            .decrypt(
+                GenericArray::from_slice(nonce),
+                Payload { msg: ciphertext, aad },
+            )
+            .map_err(|_| Error::Crypto)
+    }
+
+    fn sha256(&self, bytes: &[u8]) -> Result<[u8; 32]> {
+        let mut hasher = Sha256::new();
+        hasher.update(bytes);
+
+        Ok(hasher.finalize().into())
+    }
+}
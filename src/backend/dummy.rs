@@ -0,0 +1,111 @@
+//! A non-secure [`CryptoBackend`] stand-in for tests.
+//!
+//! `DummyBackend` skips real cryptography in favor of cheap, deterministic
+//! operations (XOR instead of AEAD, truncation instead of a real KDF, ...).
+//! It exists purely so tests that exercise the COSE/EDHOC call sites don't
+//! pay for and depend on real key material. Never enable the `dummy-backend`
+//! feature outside of tests.
+
+use alloc::vec::Vec;
+
+use super::CryptoBackend;
+use crate::cose::SignatureAlg;
+use crate::{Error, Result};
+
+/// A crypto backend that performs no real cryptography.
+#[derive(Default)]
+pub struct DummyBackend;
+
+impl CryptoBackend for DummyBackend {
+    fn sign(
+        &self,
+        _alg: SignatureAlg,
+        keypair_bytes: &[u8],
+        bytes: &[u8],
+    ) -> Result<Vec<u8>> {
+        Ok(self.sha256(&[keypair_bytes, bytes].concat())?.to_vec())
+    }
+
+    fn verify(
+        &self,
+        alg: SignatureAlg,
+        keypair_bytes: &[u8],
+        bytes: &[u8],
+        signature: &[u8],
+    ) -> Result<()> {
+        if self.sign(alg, keypair_bytes, bytes)? == signature {
+            Ok(())
+        } else {
+            Err(Error::Crypto)
+        }
+    }
+
+    fn ecdh_public(&self, private_key: &[u8; 32]) -> Result<[u8; 32]> {
+        Ok(*private_key)
+    }
+
+    fn ecdh(
+        &self,
+        private_key: &[u8; 32],
+        public_key: &[u8; 32],
+    ) -> Result<[u8; 32]> {
+        let mut shared = [0; 32];
+        for i in 0..32 {
+            shared[i] = private_key[i] ^ public_key[i];
+        }
+
+        Ok(shared)
+    }
+
+    fn hkdf_expand(
+        &self,
+        prk: &[u8],
+        info: &[u8],
+        length: usize,
+    ) -> Result<Vec<u8>> {
+        let digest = self.sha256(&[prk, info].concat())?;
+
+        Ok(digest.iter().cycle().take(length).copied().collect())
+    }
+
+    fn aead_seal(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        _aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>> {
+        let mut out = xor_with_keystream(key, nonce, plaintext);
+        out.extend_from_slice(&[0; 8]);
+
+        Ok(out)
+    }
+
+    fn aead_open(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        _aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>> {
+        let len = ciphertext.len().checked_sub(8).ok_or(Error::Crypto)?;
+
+        Ok(xor_with_keystream(key, nonce, &ciphertext[..len]))
+    }
+
+    fn sha256(&self, bytes: &[u8]) -> Result<[u8; 32]> {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        hasher.update(bytes);
+
+        Ok(hasher.finalize().into())
+    }
+}
+
+fn xor_with_keystream(key: &[u8], nonce: &[u8], data: &[u8]) -> Vec<u8> {
+    data.iter()
+        .enumerate()
+        .map(|(i, b)| b ^ key[i % key.len()] ^ nonce[i % nonce.len()])
+        .collect()
+}
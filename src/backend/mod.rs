@@ -0,0 +1,99 @@
+//! Pluggable cryptographic backends.
+//!
+//! Every primitive the COSE/EDHOC layer needs (signing, ECDH, HKDF, AEAD,
+//! hashing) goes through the [`CryptoBackend`] trait, so swapping backends
+//! doesn't touch the protocol code. The one exception is the EDHOC helper
+//! that expands a 32-byte seed into an Ed25519 keypair, which derives the
+//! public key with `ed25519-dalek` directly whatever the backend.
+//!
+//! [`default::DefaultBackend`] is the pure-Rust implementation (built on
+//! `ed25519-dalek`, `p256`, `x25519-dalek`, `hkdf`, `aes-ccm`) this crate
+//! always compiles in, since it's what the EDHOC typestate API
+//! ([`crate::edhoc`]) and this crate's own tests default their generic
+//! backend parameter to. Two additional, strictly opt-in backends are
+//! selected through Cargo features instead:
+//! * `psa-backend` - [`psa::PsaBackend`], a placeholder for targets with a
+//!   PSA Crypto API implementation; every one of its primitives returns
+//!   `Err(Error::Crypto)`, as this crate bundles no PSA client.
+//! * `dummy-backend` - [`dummy::DummyBackend`], a non-secure backend used
+//!   in tests that need a cheap, deterministic stand-in.
+
+use alloc::vec::Vec;
+
+use crate::cose::SignatureAlg;
+use crate::Result;
+
+pub mod default;
+#[cfg(feature = "dummy-backend")]
+pub mod dummy;
+#[cfg(feature = "psa-backend")]
+pub mod psa;
+
+pub use default::DefaultBackend;
+
+/// A cryptographic service provider for the COSE/EDHOC layer.
+///
+/// Implementors provide the actual primitives, so the same `sign`/`verify`/
+/// `build_kdf_context` call sites work unchanged against any backend.
+pub trait CryptoBackend {
+    /// Signs `bytes` with `keypair_bytes` under the given signature
+    /// algorithm, returning the raw (non-DER) signature.
+    fn sign(
+        &self,
+        alg: SignatureAlg,
+        keypair_bytes: &[u8],
+        bytes: &[u8],
+    ) -> Result<Vec<u8>>;
+
+    /// Verifies `signature` over `bytes` under the given signature
+    /// algorithm and public key.
+    fn verify(
+        &self,
+        alg: SignatureAlg,
+        public_key: &[u8],
+        bytes: &[u8],
+        signature: &[u8],
+    ) -> Result<()>;
+
+    /// Returns the X25519 public key corresponding to `private_key`.
+    fn ecdh_public(&self, private_key: &[u8; 32]) -> Result<[u8; 32]>;
+
+    /// Performs X25519 Diffie-Hellman, returning the raw shared secret.
+    fn ecdh(
+        &self,
+        private_key: &[u8; 32],
+        public_key: &[u8; 32],
+    ) -> Result<[u8; 32]>;
+
+    /// HKDF-Expand (RFC 5869) over the given pseudorandom key, producing
+    /// `length` bytes of output keying material.
+    fn hkdf_expand(
+        &self,
+        prk: &[u8],
+        info: &[u8],
+        length: usize,
+    ) -> Result<Vec<u8>>;
+
+    /// AEAD-seals `plaintext` under `key`/`nonce`, authenticating
+    /// `aad`, appending the tag to the returned ciphertext.
+    fn aead_seal(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>>;
+
+    /// AEAD-opens `ciphertext` (with the tag appended) under `key`/`nonce`,
+    /// authenticating `aad`.
+    fn aead_open(
+        &self,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>>;
+
+    /// Returns the SHA-256 digest of `bytes`.
+    fn sha256(&self, bytes: &[u8]) -> Result<[u8; 32]>;
+}
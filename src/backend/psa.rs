@@ -0,0 +1,86 @@
+//! A placeholder [`CryptoBackend`] for PSA Crypto API targets; every
+//! primitive returns `Err(Error::Crypto)`.
+//!
+//! This crate does not bundle a PSA client; enabling `psa-backend` is
+//! expected to be paired with a platform-specific crate providing one, with
+//! the primitives below filled in against it.
+
+use alloc::vec::Vec;
+
+use super::CryptoBackend;
+use crate::cose::SignatureAlg;
+use crate::{Error, Result};
+
+/// A crypto backend backed by a PSA Crypto API implementation.
+///
+/// Left unimplemented in this crate: integrators enabling `psa-backend` are
+/// expected to provide the PSA client bindings appropriate for their target
+/// and fill in the primitives below.
+#[derive(Default)]
+pub struct PsaBackend;
+
+impl CryptoBackend for PsaBackend {
+    fn sign(
+        &self,
+        _alg: SignatureAlg,
+        _keypair_bytes: &[u8],
+        _bytes: &[u8],
+    ) -> Result<Vec<u8>> {
+        Err(Error::Crypto)
+    }
+
+    fn verify(
+        &self,
+        _alg: SignatureAlg,
+        _public_key: &[u8],
+        _bytes: &[u8],
+        _signature: &[u8],
+    ) -> Result<()> {
+        Err(Error::Crypto)
+    }
+
+    fn ecdh_public(&self, _private_key: &[u8; 32]) -> Result<[u8; 32]> {
+        Err(Error::Crypto)
+    }
+
+    fn ecdh(
+        &self,
+        _private_key: &[u8; 32],
+        _public_key: &[u8; 32],
+    ) -> Result<[u8; 32]> {
+        Err(Error::Crypto)
+    }
+
+    fn hkdf_expand(
+        &self,
+        _prk: &[u8],
+        _info: &[u8],
+        _length: usize,
+    ) -> Result<Vec<u8>> {
+        Err(Error::Crypto)
+    }
+
+    fn aead_seal(
+        &self,
+        _key: &[u8],
+        _nonce: &[u8],
+        _aad: &[u8],
+        _plaintext: &[u8],
+    ) -> Result<Vec<u8>> {
+        Err(Error::Crypto)
+    }
+
+    fn aead_open(
+        &self,
+        _key: &[u8],
+        _nonce: &[u8],
+        _aad: &[u8],
+        _ciphertext: &[u8],
+    ) -> Result<Vec<u8>> {
+        Err(Error::Crypto)
+    }
+
+    fn sha256(&self, _bytes: &[u8]) -> Result<[u8; 32]> {
+        Err(Error::Crypto)
+    }
+}
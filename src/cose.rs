@@ -1,54 +1,74 @@
 use alloc::vec::Vec;
-use ed25519_dalek::{Keypair, Signature};
 use serde_bytes::{ByteBuf, Bytes};
-use sha2::Sha512;
 
-use crate::{cbor, Result};
+use crate::backend::CryptoBackend;
+use crate::{cbor, Error, Result};
+
+/// The COSE signature algorithm to use for [`sign`]/[`verify`].
+///
+/// These correspond to the COSE algorithm identifiers from the IANA COSE
+/// Algorithms registry that this crate currently supports as EDHOC signature
+/// suites.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SignatureAlg {
+    /// EdDSA (COSE algorithm -8), using Ed25519 with SHA-512.
+    EdDsa,
+    /// ECDSA with SHA-256 (COSE algorithm -7), using the NIST P-256 curve.
+    Es256,
+}
 
 /// Returns the signature from signing the `Sig_structure` of the given data.
 ///
 /// # Arguments
+/// * `backend` - The [`CryptoBackend`] to sign with, e.g.
+///   [`backend::DefaultBackend`](crate::backend::DefaultBackend).
 /// * `id_cred_x` - The CBOR encoded header map identifying a public
 ///   authentication key, e.g. `{ 4 : h'1111' }`.
 /// * `th_i` - The transcript hash.
 /// * `cred_x` - Encoded `COSE_Key`.
-/// * `keypair_bytes` - The ed25519 authentication key pair. First 32 bytes are
-///   the secret key, the other 32 bytes the public key.
+/// * `keypair_bytes` - The authentication key pair. For `EdDsa`, the first 32
+///   bytes are the secret key and the other 32 bytes the public key. For
+///   `Es256`, this is the 32-byte private scalar.
+/// * `alg` - The signature algorithm to sign with.
 pub fn sign(
+    backend: &impl CryptoBackend,
     id_cred_x: &[u8],
     th_i: &[u8],
     cred_x: &[u8],
     keypair_bytes: &[u8],
-) -> Result<[u8; 64]> {
+    alg: SignatureAlg,
+) -> Result<Vec<u8>> {
     let to_be_signed = build_to_be_signed(id_cred_x, th_i, cred_x)?;
-    let keypair = Keypair::from_bytes(&keypair_bytes)?;
-    let signature = keypair.sign::<Sha512>(&to_be_signed);
 
-    Ok(signature.to_bytes())
+    backend.sign(alg, keypair_bytes, &to_be_signed)
 }
 
 /// Checks if the signature was made on a `Sig_structure` of the given data,
 /// with the given key.
 ///
 /// # Arguments
+/// * `backend` - The [`CryptoBackend`] to verify with, e.g.
+///   [`backend::DefaultBackend`](crate::backend::DefaultBackend).
 /// * `id_cred_x` - The CBOR encoded header map identifying a public
 ///   authentication key, e.g. `{ 4 : h'1111' }`.
 /// * `th_i` - The transcript hash.
 /// * `cred_x` - Encoded `COSE_Key`.
-/// * `public_key` - The ed25519 public key of the pair used for the signature.
-/// * `signature` - The ed25519 signature.
+/// * `public_key` - The public key of the pair used for the signature (an
+///   Ed25519 public key, or an uncompressed P-256 point for `Es256`).
+/// * `signature` - The raw signature bytes (64 bytes for either algorithm).
+/// * `alg` - The signature algorithm the signature was produced with.
 pub fn verify(
+    backend: &impl CryptoBackend,
     id_cred_x: &[u8],
     th_i: &[u8],
     cred_x: &[u8],
     public_key: &[u8],
     signature: &[u8],
+    alg: SignatureAlg,
 ) -> Result<()> {
     let to_be_signed = build_to_be_signed(id_cred_x, th_i, cred_x)?;
-    let public_key = ed25519_dalek::PublicKey::from_bytes(public_key)?;
-    let signature = Signature::from_bytes(signature)?;
 
-    Ok(public_key.verify::<Sha512>(&to_be_signed, &signature)?)
+    backend.verify(alg, public_key, &to_be_signed, signature)
 }
 
 /// Returns the COSE `Sig_structure` used as input to the signature algorithm.
@@ -89,49 +109,236 @@ pub fn build_kdf_context(
     cbor::encode(cose_kdf_context)
 }
 
-/// An Octet Key Pair (OKP) `COSE_Key`.
+/// The COSE key type (`kty`) label values this crate knows how to handle.
+const KTY_OKP: i64 = 1;
+const KTY_EC2: i64 = 2;
+
+/// A `COSE_Key`, covering the shapes this crate's signature suites need.
+///
+/// * `Okp` is an Octet Key Pair (`kty = 1`), used for the X25519 and
+///   Ed25519 keys of the `EdDsa` suite, represented as a single
+///   x-coordinate.
+/// * `Ec2` is a double-coordinate elliptic curve key (`kty = 2`), used for
+///   the P-256 keys of the `Es256` suite.
 #[derive(Debug, PartialEq)]
-pub struct CoseKey {
-    crv: usize,
-    x: Vec<u8>,
-    kty: usize,
-    kid: Vec<u8>,
+pub enum CoseKey {
+    /// An Octet Key Pair `COSE_Key`.
+    Okp {
+        /// The COSE curve identifier (`crv`), e.g. 4 for X25519.
+        crv: i64,
+        /// The x-coordinate.
+        x: Vec<u8>,
+        /// The key ID (`kid`).
+        kid: Vec<u8>,
+    },
+    /// An EC2 `COSE_Key`.
+    Ec2 {
+        /// The COSE curve identifier (`crv`), e.g. 1 for P-256.
+        crv: i64,
+        /// The x-coordinate.
+        x: Vec<u8>,
+        /// The y-coordinate.
+        y: Vec<u8>,
+        /// The key ID (`kid`).
+        kid: Vec<u8>,
+    },
 }
 
-/// Returns the CBOR encoded `COSE_Key` for the given data.
-///
-/// This is specific to our use case where we only have X25519 public keys,
-/// which are Octet Key Pairs (OKP) in COSE and represented as a single
-/// x-coordinate.
-pub fn serialize_cose_key(x: &[u8], kid: &[u8]) -> Result<Vec<u8>> {
+/// Returns the CBOR encoded `COSE_Key` for the given key.
+pub fn serialize_cose_key(key: &CoseKey) -> Result<Vec<u8>> {
     // Pack the data into a structure that nicely serializes almost into
-    // what we want to have as the actual bytes for the COSE_Key.
-    // (crv key, crv value, x-coordinate key, x-coordinate value,
-    //  kty key, kty value, kid key, kid value)
-    let raw_key = (-1, 4, -2, Bytes::new(x), 1, 1, 2, Bytes::new(kid));
-    // Get the byte representation of it
-    let mut bytes = cbor::encode(raw_key)?;
-    // This is a CBOR array, but we want a map
+    // what we want to have as the actual bytes for the COSE_Key, then turn
+    // the resulting CBOR array into a map.
+    let mut bytes = match key {
+        CoseKey::Okp { crv, x, kid } => {
+            // (crv key, crv value, x key, x value, kty key, kty value,
+            //  kid key, kid value)
+            let raw_key =
+                (-1, *crv, -2, Bytes::new(x), 1, KTY_OKP, 2, Bytes::new(kid));
+            cbor::encode(raw_key)?
+        }
+        CoseKey::Ec2 { crv, x, y, kid } => {
+            // (crv key, crv value, x key, x value, y key, y value,
+            //  kty key, kty value, kid key, kid value)
+            let raw_key = (
+                -1,
+                *crv,
+                -2,
+                Bytes::new(x),
+                -3,
+                Bytes::new(y),
+                1,
+                KTY_EC2,
+                2,
+                Bytes::new(kid),
+            );
+            cbor::encode(raw_key)?
+        }
+    };
     cbor::array_to_map(&mut bytes)?;
 
     Ok(bytes)
 }
 
+/// A decoded COSE map entry value: either an integer (`kty`, `crv`) or a
+/// byte string (`x`, `y`, `kid`).
+enum MapValue {
+    Int(i64),
+    Bytes(Vec<u8>),
+}
+
+/// A minimal CBOR reader, just enough to walk a `COSE_Key` map whose entries
+/// may appear in any order. `cbor::decode`/`array_to_map`/`map_to_array`
+/// elsewhere in this module only ever handle a fixed-order tuple, which
+/// isn't enough here since [`deserialize_cose_key`] must accept any label
+/// order and reject unknown/duplicate labels.
+struct MapReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> MapReader<'a> {
+    /// Reads a major type/argument pair at the front of `self.bytes`.
+    fn read_head(&mut self) -> Result<(u8, u64)> {
+        let (&first, rest) = self.bytes.split_first().ok_or(Error::Cbor)?;
+        let (argument, rest) = match first & 0x1F {
+            n @ 0..=23 => (u64::from(n), rest),
+            24 => {
+                let (&n, rest) = rest.split_first().ok_or(Error::Cbor)?;
+                (u64::from(n), rest)
+            }
+            25 => {
+                let n = rest.get(..2).ok_or(Error::Cbor)?;
+                (u64::from(u16::from_be_bytes([n[0], n[1]])), &rest[2..])
+            }
+            _ => return Err(Error::Cbor),
+        };
+
+        self.bytes = rest;
+        Ok((first >> 5, argument))
+    }
+
+    /// Reads a map header (major type 5), returning its entry count.
+    fn read_map_header(&mut self) -> Result<u64> {
+        match self.read_head()? {
+            (5, len) => Ok(len),
+            _ => Err(Error::Cbor),
+        }
+    }
+
+    /// Reads an unsigned (major type 0) or negative (major type 1) integer.
+    fn read_int(&mut self) -> Result<i64> {
+        match self.read_head()? {
+            (0, n) => i64::try_from(n).map_err(|_| Error::Cbor),
+            (1, n) => i64::try_from(n).map(|n| -1 - n).map_err(|_| Error::Cbor),
+            _ => Err(Error::Cbor),
+        }
+    }
+
+    /// Reads a byte string (major type 2).
+    fn read_bytes(&mut self) -> Result<Vec<u8>> {
+        match self.read_head()? {
+            (2, len) => {
+                let len = usize::try_from(len).map_err(|_| Error::Cbor)?;
+                let bytes = self.bytes.get(..len).ok_or(Error::Cbor)?;
+                self.bytes = &self.bytes[len..];
+
+                Ok(bytes.to_vec())
+            }
+            _ => Err(Error::Cbor),
+        }
+    }
+
+    /// Reads whichever of [`Self::read_int`]/[`Self::read_bytes`] matches
+    /// the next item's major type.
+    fn read_value(&mut self) -> Result<MapValue> {
+        match self.bytes.first().map(|b| b >> 5) {
+            Some(0) | Some(1) => self.read_int().map(MapValue::Int),
+            Some(2) => self.read_bytes().map(MapValue::Bytes),
+            _ => Err(Error::Cbor),
+        }
+    }
+}
+
+fn as_int(value: Option<MapValue>) -> Result<i64> {
+    match value {
+        Some(MapValue::Int(n)) => Ok(n),
+        _ => Err(Error::Cbor),
+    }
+}
+
+fn as_bytes(value: Option<MapValue>) -> Result<Vec<u8>> {
+    match value {
+        Some(MapValue::Bytes(b)) => Ok(b),
+        _ => Err(Error::Cbor),
+    }
+}
+
 /// Returns the `COSE_Key` structure deserialized from the given bytes.
-pub fn deserialize_cose_key(mut bytes: Vec<u8>) -> Result<CoseKey> {
-    // Turn the CBOR map into an array that we can deserialize
-    cbor::map_to_array(&mut bytes)?;
-    // Try to deserialize into our raw format
-    let raw_key: (isize, usize, isize, ByteBuf, isize, usize, isize, ByteBuf) =
-        cbor::decode(&mut bytes)?;
-
-    // On success, just move the items into the "nice" key structure
-    Ok(CoseKey {
-        crv: raw_key.1,
-        x: raw_key.3.into_vec(),
-        kty: raw_key.5,
-        kid: raw_key.7.into_vec(),
-    })
+///
+/// Unlike [`serialize_cose_key`], this reads the CBOR map generically: the
+/// labels may appear in any order, and unknown or duplicate labels are
+/// rejected, rather than assuming the fixed label order `serialize_cose_key`
+/// produces.
+pub fn deserialize_cose_key(bytes: &[u8]) -> Result<CoseKey> {
+    let mut reader = MapReader { bytes };
+    let len = reader.read_map_header()?;
+
+    let mut kty = None;
+    let mut crv = None;
+    let mut x = None;
+    let mut y = None;
+    let mut kid = None;
+
+    for _ in 0..len {
+        let label = reader.read_int()?;
+        let value = reader.read_value()?;
+        let slot = match label {
+            1 => &mut kty,
+            -1 => &mut crv,
+            -2 => &mut x,
+            -3 => &mut y,
+            2 => &mut kid,
+            // Unknown label.
+            _ => return Err(Error::Cbor),
+        };
+        // Duplicate label.
+        if slot.replace(value).is_some() {
+            return Err(Error::Cbor);
+        }
+    }
+
+    match as_int(kty)? {
+        KTY_OKP => Ok(CoseKey::Okp {
+            crv: as_int(crv)?,
+            x: as_bytes(x)?,
+            kid: as_bytes(kid)?,
+        }),
+        KTY_EC2 => Ok(CoseKey::Ec2 {
+            crv: as_int(crv)?,
+            x: as_bytes(x)?,
+            y: as_bytes(y)?,
+            kid: as_bytes(kid)?,
+        }),
+        // Missing or unsupported kty.
+        _ => Err(Error::Cbor),
+    }
+}
+
+/// Returns the uncompressed SEC1 point `0x04 || x || y` for an `Ec2`
+/// `COSE_Key`, the form `backend::DefaultBackend`'s `Es256` verification
+/// expects as its public key argument.
+pub fn ec2_public_key(key: &CoseKey) -> Result<Vec<u8>> {
+    match key {
+        CoseKey::Ec2 { x, y, .. } => {
+            let mut point = Vec::with_capacity(1 + x.len() + y.len());
+            point.push(0x04);
+            point.extend_from_slice(x);
+            point.extend_from_slice(y);
+
+            Ok(point)
+        }
+        CoseKey::Okp { .. } => Err(Error::Cbor),
+    }
 }
 
 /// Returns the COSE header map for the given `kid`.
@@ -166,6 +373,7 @@ pub fn build_ad(th_i: &[u8]) -> Result<Vec<u8>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::DefaultBackend;
 
     static ID_CRED_X: [u8; 5] = [0xA1, 0x04, 0x42, 0x11, 0x11];
     static TH_I: [u8; 3] = [0x22, 0x22, 0x22];
@@ -202,30 +410,52 @@ mod tests {
 
     #[test]
     fn signature_same() {
-        let signature = sign(&ID_CRED_X, &TH_I, &CRED_X, &KEYPAIR).unwrap();
+        let backend = DefaultBackend::default();
+        let signature = sign(
+            &backend,
+            &ID_CRED_X,
+            &TH_I,
+            &CRED_X,
+            &KEYPAIR,
+            SignatureAlg::EdDsa,
+        )
+        .unwrap();
         assert_eq!(&SIGNATURE[..], &signature[..]);
     }
 
     #[test]
     fn signature_verifies() {
-        let signature = sign(&ID_CRED_X, &TH_I, &CRED_X, &KEYPAIR).unwrap();
+        let backend = DefaultBackend::default();
+        let signature = sign(
+            &backend,
+            &ID_CRED_X,
+            &TH_I,
+            &CRED_X,
+            &KEYPAIR,
+            SignatureAlg::EdDsa,
+        )
+        .unwrap();
         assert!(verify(
+            &backend,
             &ID_CRED_X,
             &TH_I,
             &CRED_X,
             &KEYPAIR[32..],
-            &signature
+            &signature,
+            SignatureAlg::EdDsa,
         )
         .is_ok());
 
         let mut cred_x_changed = CRED_X.to_vec();
         cred_x_changed[1] = 0x44;
         assert!(verify(
+            &backend,
             &ID_CRED_X,
             &TH_I,
             &cred_x_changed,
             &KEYPAIR[32..],
-            &signature
+            &signature,
+            SignatureAlg::EdDsa,
         )
         .is_err());
     }
@@ -256,9 +486,8 @@ mod tests {
         assert_eq!(&CONTEXT2[..], &context_bytes[..]);
     }
 
-    static CURVE: usize = 4;
+    static CURVE: i64 = 4;
     static X: [u8; 4] = [0x00, 0x01, 0x02, 0x03];
-    static KTY: usize = 1;
     static KID: [u8; 4] = [0x04, 0x05, 0x06, 0x07];
     static KEY_BYTES: [u8; 17] = [
         0xA4, 0x20, 0x04, 0x21, 0x44, 0x00, 0x01, 0x02, 0x03, 0x01, 0x01,
@@ -267,20 +496,131 @@ mod tests {
 
     #[test]
     fn key_encode() {
-        assert_eq!(&KEY_BYTES[..], &serialize_cose_key(&X, &KID).unwrap()[..]);
+        let key = CoseKey::Okp {
+            crv: CURVE,
+            x: X.to_vec(),
+            kid: KID.to_vec(),
+        };
+        assert_eq!(&KEY_BYTES[..], &serialize_cose_key(&key).unwrap()[..]);
     }
 
     #[test]
     fn key_decode() {
-        let key = CoseKey {
+        let key = CoseKey::Okp {
             crv: CURVE,
             x: X.to_vec(),
-            kty: KTY,
             kid: KID.to_vec(),
         };
-        let bytes = KEY_BYTES.to_vec();
 
-        assert_eq!(key, deserialize_cose_key(bytes).unwrap());
+        assert_eq!(key, deserialize_cose_key(&KEY_BYTES).unwrap());
+    }
+
+    static EC2_CURVE: i64 = 1;
+    static EC2_X: [u8; 4] = [0x10, 0x11, 0x12, 0x13];
+    static EC2_Y: [u8; 4] = [0x20, 0x21, 0x22, 0x23];
+    static EC2_KID: [u8; 4] = [0x04, 0x05, 0x06, 0x07];
+
+    #[test]
+    fn ec2_key_roundtrips() {
+        let key = CoseKey::Ec2 {
+            crv: EC2_CURVE,
+            x: EC2_X.to_vec(),
+            y: EC2_Y.to_vec(),
+            kid: EC2_KID.to_vec(),
+        };
+        let bytes = serialize_cose_key(&key).unwrap();
+
+        assert_eq!(key, deserialize_cose_key(&bytes).unwrap());
+    }
+
+    #[test]
+    fn ec2_cred_x_verifies_es256_signature() {
+        use p256::ecdsa::SigningKey;
+        use p256::elliptic_curve::sec1::ToEncodedPoint;
+
+        let backend = DefaultBackend::default();
+        let private_key = [0x11; 32];
+        let signing_key = SigningKey::from_bytes(&private_key).unwrap();
+        let point = signing_key.verifying_key().to_encoded_point(false);
+
+        let cred_x = CoseKey::Ec2 {
+            crv: EC2_CURVE,
+            x: point.x().unwrap().to_vec(),
+            y: point.y().unwrap().to_vec(),
+            kid: EC2_KID.to_vec(),
+        };
+        let cred_x = serialize_cose_key(&cred_x).unwrap();
+
+        let signature = sign(
+            &backend,
+            &ID_CRED_X,
+            &TH_I,
+            &cred_x,
+            &private_key,
+            SignatureAlg::Es256,
+        )
+        .unwrap();
+
+        // The peer only ever sees `cred_x` on the wire; it recovers the
+        // public key it needs to verify with from that.
+        let decoded = deserialize_cose_key(&cred_x).unwrap();
+        let public_key = ec2_public_key(&decoded).unwrap();
+
+        assert!(verify(
+            &backend,
+            &ID_CRED_X,
+            &TH_I,
+            &cred_x,
+            &public_key,
+            &signature,
+            SignatureAlg::Es256,
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn es256_rejects_invalid_signature() {
+        use p256::ecdsa::SigningKey;
+        use p256::elliptic_curve::sec1::ToEncodedPoint;
+
+        let backend = DefaultBackend::default();
+        let private_key = [0x11; 32];
+        let signing_key = SigningKey::from_bytes(&private_key).unwrap();
+        let point = signing_key.verifying_key().to_encoded_point(false);
+
+        let cred_x = CoseKey::Ec2 {
+            crv: EC2_CURVE,
+            x: point.x().unwrap().to_vec(),
+            y: point.y().unwrap().to_vec(),
+            kid: EC2_KID.to_vec(),
+        };
+        let cred_x = serialize_cose_key(&cred_x).unwrap();
+        let decoded = deserialize_cose_key(&cred_x).unwrap();
+        let public_key = ec2_public_key(&decoded).unwrap();
+
+        // All-zero `r` and `s`, neither a valid scalar.
+        let signature = [0u8; 64];
+
+        assert!(verify(
+            &backend,
+            &ID_CRED_X,
+            &TH_I,
+            &cred_x,
+            &public_key,
+            &signature,
+            SignatureAlg::Es256,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn unknown_label_rejected() {
+        // Same as KEY_BYTES, but with an extra, unknown `5: 0` entry.
+        let mut bytes = KEY_BYTES.to_vec();
+        bytes[0] = 0xA5;
+        bytes.extend_from_slice(&[0x05, 0x00]);
+
+        assert!(deserialize_cose_key(&bytes).is_err());
     }
 
     static KID_2: [u8; 2] = [0x00, 0x01];
@@ -0,0 +1,639 @@
+//! The EDHOC handshake, modeled as a typestate machine.
+//!
+//! Party U drives `Msg1Sender -> Msg2Receiver -> Msg3Sender`, Party V
+//! drives `Msg1Receiver -> Msg2Sender -> Msg3Receiver`. Each step consumes
+//! the previous state and returns the next one (or the final OSCORE
+//! Master Secret/Master Salt pair), so a state can only ever be driven
+//! once. Handling a peer's message can fail in two ways, both of which
+//! consume the state and leave nothing further to call:
+//! * we detect the problem (a malformed message, a signature that doesn't
+//!   verify, ...) and the call returns
+//!   `Err(OwnOrPeerError::Own(error_message_bytes))`, which the caller
+//!   must send to the other party;
+//! * the other party already gave up and sent us an EDHOC `error` message
+//!   instead of the expected one, and the call returns
+//!   `Err(OwnOrPeerError::Peer(diagnostic))`.
+//!
+//! A party can also abandon the exchange on its own initiative (e.g. an
+//! application-level policy check failed) by calling `abandon` on its
+//! current state, which consumes it and returns the `error` message to
+//! send.
+//!
+//! Building and sending a message (`with_backend`, `generate_message_*`)
+//! has no peer to report a failure to, so those return a plain
+//! [`crate::Result`] instead of `OwnOrPeerError`: a [`CryptoBackend`] that
+//! can genuinely fail (a hardware/secure-element backend, say) surfaces
+//! that as `Err(Error::Crypto)` rather than panicking.
+
+use alloc::vec::Vec;
+use serde_bytes::{ByteBuf, Bytes};
+
+use super::error::{build_error_message, OwnError, OwnOrPeerError};
+use super::util;
+use crate::backend::{CryptoBackend, DefaultBackend};
+use crate::cose::{self, CoseKey, SignatureAlg};
+use crate::{cbor, Result};
+
+/// The COSE curve identifier (X25519) of the ephemeral public key this
+/// crate puts in `CRED_x`.
+const OKP_X25519_CRV: i64 = 4;
+
+/// Party U, about to send message_1.
+pub struct Msg1Sender<B: CryptoBackend = DefaultBackend> {
+    backend: B,
+    c_u: Vec<u8>,
+    x: [u8; 32],
+    g_x: [u8; 32],
+}
+
+impl Msg1Sender<DefaultBackend> {
+    /// Creates a new `Msg1Sender` using the default crypto backend.
+    ///
+    /// # Arguments
+    /// * `c_u` - Own connection identifier.
+    /// * `x` - Own ephemeral ECDH private key. For simplicity, this crate
+    ///   also uses it as the Ed25519 authentication key (see
+    ///   [`util::expand_ed25519_keypair`]). [`Msg1Sender::with_backend`]
+    ///   does the same; neither takes a separate authentication key.
+    pub fn new(c_u: &[u8], x: [u8; 32]) -> Self {
+        Self::with_backend(c_u, x, DefaultBackend::default())
+            .expect("the default backend cannot fail")
+    }
+}
+
+impl<B: CryptoBackend> Msg1Sender<B> {
+    /// Creates a new `Msg1Sender` using a specific [`CryptoBackend`].
+    pub fn with_backend(c_u: &[u8], x: [u8; 32], backend: B) -> Result<Self> {
+        let g_x = backend.ecdh_public(&x)?;
+
+        Ok(Msg1Sender { backend, c_u: c_u.to_vec(), x, g_x })
+    }
+
+    /// Builds message_1 and returns it along with the next state.
+    pub fn generate_message_1(self) -> Result<(Vec<u8>, Msg2Receiver<B>)> {
+        // (C_u, G_X)
+        let message_1 = cbor::encode((Bytes::new(&self.c_u), Bytes::new(&self.g_x)))
+            .expect("encoding message_1 cannot fail");
+        let th_1 = util::transcript_hash(&self.backend, &[], &message_1)?;
+
+        Ok((
+            message_1,
+            Msg2Receiver {
+                backend: self.backend,
+                c_u: self.c_u,
+                x: self.x,
+                g_x: self.g_x,
+                th_1,
+            },
+        ))
+    }
+
+    /// Abandons the exchange, returning the `error` message to send.
+    pub fn abandon(self, err_msg: &str) -> OwnError {
+        build_error_message(&[], err_msg)
+            .expect("encoding an error message cannot fail")
+    }
+}
+
+/// Party U, waiting to receive message_2.
+pub struct Msg2Receiver<B: CryptoBackend = DefaultBackend> {
+    backend: B,
+    c_u: Vec<u8>,
+    x: [u8; 32],
+    g_x: [u8; 32],
+    th_1: Vec<u8>,
+}
+
+impl<B: CryptoBackend> Msg2Receiver<B> {
+    /// Processes message_2, verifying it against `v_public_key` (Party
+    /// V's long-term Ed25519 authentication public key, known out of
+    /// band), and returns the next state.
+    pub fn handle_message_2(
+        self,
+        message_2: &mut Vec<u8>,
+        v_public_key: [u8; 32],
+    ) -> Result<Msg3Sender<B>, OwnOrPeerError> {
+        let raw = message_2.clone();
+        let (c_v, g_y, ciphertext_2): (ByteBuf, ByteBuf, ByteBuf) =
+            match cbor::decode(message_2) {
+                Ok(decoded) => decoded,
+                Err(_) => return Err(self.peer_or_own_error(&raw, "malformed message_2")),
+            };
+        let c_v = c_v.into_vec();
+        let g_y: [u8; 32] =
+            g_y.into_vec().try_into().map_err(|_| self.own_error(&c_v, "G_Y has the wrong length"))?;
+
+        let message_2_partial =
+            cbor::encode((Bytes::new(&c_v), Bytes::new(&g_y)))
+                .expect("encoding cannot fail");
+        let th_2 = util::transcript_hash(&self.backend, &self.th_1, &message_2_partial)
+            .map_err(|_| self.own_error(&c_v, "failed to hash message_2"))?;
+
+        let prk = self
+            .backend
+            .ecdh(&self.x, &g_y)
+            .map_err(|_| self.own_error(&c_v, "key agreement failed"))?;
+        let aad = cose::build_ad(&th_2)
+            .map_err(|_| self.own_error(&c_v, "failed to build AAD"))?;
+        let (key, iv) = util::derive_aead_key_iv(&self.backend, &prk, &th_2)
+            .map_err(|_| self.own_error(&c_v, "key derivation failed"))?;
+        let plaintext = self
+            .backend
+            .aead_open(&key, &iv, &aad, &ciphertext_2)
+            .map_err(|_| self.own_error(&c_v, "decryption of message_2 failed"))?;
+
+        let (id_cred_v, sig_v): (ByteBuf, ByteBuf) =
+            cbor::decode(&mut plaintext.clone())
+                .map_err(|_| self.own_error(&c_v, "malformed message_2 payload"))?;
+
+        let cred_v = cose::serialize_cose_key(&CoseKey::Okp {
+            crv: OKP_X25519_CRV,
+            x: g_y.to_vec(),
+            kid: c_v.clone(),
+        })
+        .map_err(|_| self.own_error(&c_v, "failed to rebuild CRED_V"))?;
+
+        cose::verify(
+            &self.backend,
+            &id_cred_v,
+            &th_2,
+            &cred_v,
+            &v_public_key,
+            &sig_v,
+            SignatureAlg::EdDsa,
+        )
+        .map_err(|_| self.own_error(&c_v, "signature verification of message_2 failed"))?;
+
+        Ok(Msg3Sender {
+            backend: self.backend,
+            c_u: self.c_u,
+            x: self.x,
+            prk,
+            th_2,
+            c_v,
+        })
+    }
+
+    /// Abandons the exchange, returning the `error` message to send.
+    pub fn abandon(self, err_msg: &str) -> OwnError {
+        build_error_message(&[], err_msg)
+            .expect("encoding an error message cannot fail")
+    }
+
+    fn own_error(&self, c_v: &[u8], err_msg: &str) -> OwnOrPeerError {
+        OwnOrPeerError::Own(
+            build_error_message(c_v, err_msg)
+                .expect("encoding an error message cannot fail")
+                .0,
+        )
+    }
+
+    fn peer_or_own_error(&self, raw: &[u8], err_msg: &str) -> OwnOrPeerError {
+        match super::error::parse_error_message(raw) {
+            Ok((_, diagnostic)) => OwnOrPeerError::Peer(diagnostic),
+            Err(_) => self.own_error(&[], err_msg),
+        }
+    }
+}
+
+/// Party V, waiting to receive message_1.
+pub struct Msg1Receiver<B: CryptoBackend = DefaultBackend> {
+    backend: B,
+    c_v: Vec<u8>,
+    y: [u8; 32],
+    g_y: [u8; 32],
+}
+
+impl Msg1Receiver<DefaultBackend> {
+    /// Creates a new `Msg1Receiver` using the default crypto backend.
+    ///
+    /// # Arguments
+    /// * `c_v` - Own connection identifier.
+    /// * `y` - Own ephemeral ECDH private key (see [`Msg1Sender::new`] for
+    ///   why this is also used as the Ed25519 authentication key here).
+    pub fn new(c_v: &[u8], y: [u8; 32]) -> Self {
+        Self::with_backend(c_v, y, DefaultBackend::default())
+            .expect("the default backend cannot fail")
+    }
+}
+
+impl<B: CryptoBackend> Msg1Receiver<B> {
+    /// Creates a new `Msg1Receiver` using a specific [`CryptoBackend`].
+    pub fn with_backend(c_v: &[u8], y: [u8; 32], backend: B) -> Result<Self> {
+        let g_y = backend.ecdh_public(&y)?;
+
+        Ok(Msg1Receiver { backend, c_v: c_v.to_vec(), y, g_y })
+    }
+
+    /// Processes message_1 and returns the next state.
+    pub fn handle_message_1(
+        self,
+        message_1: &mut Vec<u8>,
+    ) -> Result<Msg2Sender<B>, OwnOrPeerError> {
+        let raw = message_1.clone();
+        let (c_u, g_x): (ByteBuf, ByteBuf) = match cbor::decode(message_1) {
+            Ok(decoded) => decoded,
+            Err(_) => {
+                return Err(match super::error::parse_error_message(&raw) {
+                    Ok((_, diagnostic)) => OwnOrPeerError::Peer(diagnostic),
+                    Err(_) => OwnOrPeerError::Own(
+                        build_error_message(&[], "malformed message_1")
+                            .expect("encoding an error message cannot fail")
+                            .0,
+                    ),
+                })
+            }
+        };
+        let c_u = c_u.into_vec();
+        let g_x: [u8; 32] = g_x.into_vec().try_into().map_err(|_| {
+            OwnOrPeerError::Own(
+                build_error_message(&c_u, "G_X has the wrong length")
+                    .expect("encoding an error message cannot fail")
+                    .0,
+            )
+        })?;
+
+        let th_1 = util::transcript_hash(&self.backend, &[], &raw).map_err(|_| {
+            OwnOrPeerError::Own(
+                build_error_message(&c_u, "failed to hash message_1")
+                    .expect("encoding an error message cannot fail")
+                    .0,
+            )
+        })?;
+
+        Ok(Msg2Sender {
+            backend: self.backend,
+            c_v: self.c_v,
+            y: self.y,
+            g_y: self.g_y,
+            c_u,
+            g_x,
+            th_1,
+        })
+    }
+
+    /// Abandons the exchange, returning the `error` message to send.
+    pub fn abandon(self, err_msg: &str) -> OwnError {
+        build_error_message(&[], err_msg)
+            .expect("encoding an error message cannot fail")
+    }
+}
+
+/// Party V, about to send message_2.
+pub struct Msg2Sender<B: CryptoBackend = DefaultBackend> {
+    backend: B,
+    c_v: Vec<u8>,
+    y: [u8; 32],
+    g_y: [u8; 32],
+    c_u: Vec<u8>,
+    g_x: [u8; 32],
+    th_1: Vec<u8>,
+}
+
+impl<B: CryptoBackend> Msg2Sender<B> {
+    /// Builds message_2 and returns it along with the next state.
+    pub fn generate_message_2(self) -> Result<(Vec<u8>, Msg3Receiver<B>)> {
+        let message_2_partial =
+            cbor::encode((Bytes::new(&self.c_v), Bytes::new(&self.g_y)))
+                .expect("encoding cannot fail");
+        let th_2 =
+            util::transcript_hash(&self.backend, &self.th_1, &message_2_partial)?;
+
+        let prk = self.backend.ecdh(&self.y, &self.g_x)?;
+
+        let id_cred_v = cose::build_id_cred_x(&self.c_v)
+            .expect("encoding ID_CRED_V cannot fail");
+        let cred_v = cose::serialize_cose_key(&CoseKey::Okp {
+            crv: OKP_X25519_CRV,
+            x: self.g_y.to_vec(),
+            kid: self.c_v.clone(),
+        })
+        .expect("encoding CRED_V cannot fail");
+        let keypair_v = util::expand_ed25519_keypair(&self.y)
+            .expect("expanding the Ed25519 keypair cannot fail");
+        let sig_v = cose::sign(
+            &self.backend,
+            &id_cred_v,
+            &th_2,
+            &cred_v,
+            &keypair_v,
+            SignatureAlg::EdDsa,
+        )?;
+
+        let plaintext = cbor::encode((Bytes::new(&id_cred_v), Bytes::new(&sig_v)))
+            .expect("encoding cannot fail");
+        let aad = cose::build_ad(&th_2).expect("encoding AAD cannot fail");
+        let (key, iv) = util::derive_aead_key_iv(&self.backend, &prk, &th_2)?;
+        let ciphertext_2 =
+            self.backend.aead_seal(&key, &iv, &aad, &plaintext)?;
+
+        let message_2 = cbor::encode((
+            Bytes::new(&self.c_v),
+            Bytes::new(&self.g_y),
+            Bytes::new(&ciphertext_2),
+        ))
+        .expect("encoding message_2 cannot fail");
+
+        Ok((
+            message_2,
+            Msg3Receiver {
+                backend: self.backend,
+                prk,
+                th_2,
+                c_v: self.c_v,
+                c_u: self.c_u,
+                g_x: self.g_x,
+            },
+        ))
+    }
+
+    /// Abandons the exchange, returning the `error` message to send.
+    pub fn abandon(self, err_msg: &str) -> OwnError {
+        build_error_message(&self.c_u, err_msg)
+            .expect("encoding an error message cannot fail")
+    }
+}
+
+/// Party U, about to send message_3.
+pub struct Msg3Sender<B: CryptoBackend = DefaultBackend> {
+    backend: B,
+    c_u: Vec<u8>,
+    x: [u8; 32],
+    prk: [u8; 32],
+    th_2: Vec<u8>,
+    c_v: Vec<u8>,
+}
+
+impl<B: CryptoBackend> Msg3Sender<B> {
+    /// Builds message_3 and returns it along with the freshly established
+    /// OSCORE Master Secret and Master Salt.
+    pub fn generate_message_3(self) -> Result<(Vec<u8>, [u8; 16], [u8; 8])> {
+        let g_x = self.backend.ecdh_public(&self.x)?;
+
+        let id_cred_u = cose::build_id_cred_x(&self.c_u)
+            .expect("encoding ID_CRED_U cannot fail");
+        let cred_u = cose::serialize_cose_key(&CoseKey::Okp {
+            crv: OKP_X25519_CRV,
+            x: g_x.to_vec(),
+            kid: self.c_u.clone(),
+        })
+        .expect("encoding CRED_U cannot fail");
+        let keypair_u = util::expand_ed25519_keypair(&self.x)
+            .expect("expanding the Ed25519 keypair cannot fail");
+        let sig_u = cose::sign(
+            &self.backend,
+            &id_cred_u,
+            &self.th_2,
+            &cred_u,
+            &keypair_u,
+            SignatureAlg::EdDsa,
+        )?;
+
+        let plaintext = cbor::encode((Bytes::new(&id_cred_u), Bytes::new(&sig_u)))
+            .expect("encoding cannot fail");
+
+        let th_3 =
+            util::transcript_hash(&self.backend, &self.th_2, b"message_3")?;
+        let aad = cose::build_ad(&th_3).expect("encoding AAD cannot fail");
+        let (key, iv) =
+            util::derive_aead_key_iv(&self.backend, &self.prk, &th_3)?;
+        let message_3 = self.backend.aead_seal(&key, &iv, &aad, &plaintext)?;
+
+        let (master_secret, master_salt) =
+            util::derive_oscore_context(&self.backend, &self.prk, &th_3)?;
+
+        Ok((message_3, master_secret, master_salt))
+    }
+
+    /// Abandons the exchange, returning the `error` message to send.
+    pub fn abandon(self, err_msg: &str) -> OwnError {
+        build_error_message(&self.c_v, err_msg)
+            .expect("encoding an error message cannot fail")
+    }
+}
+
+/// Party V, waiting to receive message_3.
+pub struct Msg3Receiver<B: CryptoBackend = DefaultBackend> {
+    backend: B,
+    prk: [u8; 32],
+    th_2: Vec<u8>,
+    c_v: Vec<u8>,
+    c_u: Vec<u8>,
+    g_x: [u8; 32],
+}
+
+impl<B: CryptoBackend> Msg3Receiver<B> {
+    /// Processes message_3, verifying it against `u_public_key` (Party
+    /// U's long-term Ed25519 authentication public key, known out of
+    /// band), and returns the freshly established OSCORE Master Secret
+    /// and Master Salt.
+    pub fn handle_message_3(
+        self,
+        message_3: &mut Vec<u8>,
+        u_public_key: [u8; 32],
+    ) -> Result<([u8; 16], [u8; 8]), OwnOrPeerError> {
+        let th_3 = util::transcript_hash(&self.backend, &self.th_2, b"message_3")
+            .map_err(|_| self.own_error("failed to hash message_3"))?;
+        let aad = cose::build_ad(&th_3)
+            .map_err(|_| self.own_error("failed to build AAD"))?;
+        let (key, iv) = util::derive_aead_key_iv(&self.backend, &self.prk, &th_3)
+            .map_err(|_| self.own_error("key derivation failed"))?;
+
+        let plaintext = match self.backend.aead_open(&key, &iv, &aad, message_3) {
+            Ok(plaintext) => plaintext,
+            Err(_) => {
+                return Err(match super::error::parse_error_message(message_3) {
+                    Ok((_, diagnostic)) => OwnOrPeerError::Peer(diagnostic),
+                    Err(_) => self.own_error("decryption of message_3 failed"),
+                })
+            }
+        };
+
+        let (id_cred_u, sig_u): (ByteBuf, ByteBuf) =
+            cbor::decode(&mut plaintext.clone())
+                .map_err(|_| self.own_error("malformed message_3 payload"))?;
+
+        let cred_u = cose::serialize_cose_key(&CoseKey::Okp {
+            crv: OKP_X25519_CRV,
+            x: self.g_x.to_vec(),
+            kid: self.c_u.clone(),
+        })
+        .map_err(|_| self.own_error("failed to rebuild CRED_U"))?;
+
+        cose::verify(
+            &self.backend,
+            &id_cred_u,
+            &self.th_2,
+            &cred_u,
+            &u_public_key,
+            &sig_u,
+            SignatureAlg::EdDsa,
+        )
+        .map_err(|_| self.own_error("signature verification of message_3 failed"))?;
+
+        util::derive_oscore_context(&self.backend, &self.prk, &th_3)
+            .map_err(|_| self.own_error("deriving the OSCORE context failed"))
+    }
+
+    /// Abandons the exchange, returning the `error` message to send.
+    pub fn abandon(self, err_msg: &str) -> OwnError {
+        build_error_message(&self.c_u, err_msg)
+            .expect("encoding an error message cannot fail")
+    }
+
+    fn own_error(&self, err_msg: &str) -> OwnOrPeerError {
+        OwnOrPeerError::Own(
+            build_error_message(&self.c_u, err_msg)
+                .expect("encoding an error message cannot fail")
+                .0,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const U_C_U: &[u8] = b"Party U";
+    const V_C_V: &[u8] = b"Party V";
+    const U_PRIV: [u8; 32] = [0x11; 32];
+    const V_PRIV: [u8; 32] = [0x22; 32];
+
+    fn public_key(seed: &[u8; 32]) -> [u8; 32] {
+        let keypair = util::expand_ed25519_keypair(seed).unwrap();
+        let mut public = [0; 32];
+        public.copy_from_slice(&keypair[32..]);
+        public
+    }
+
+    #[test]
+    fn full_handshake_agrees_on_oscore_context() {
+        let u_public = public_key(&U_PRIV);
+        let v_public = public_key(&V_PRIV);
+
+        let (mut msg1_bytes, msg2_receiver) =
+            Msg1Sender::new(U_C_U, U_PRIV).generate_message_1().unwrap();
+        let msg2_sender = Msg1Receiver::new(V_C_V, V_PRIV)
+            .handle_message_1(&mut msg1_bytes)
+            .unwrap();
+        let (mut msg2_bytes, msg3_receiver) =
+            msg2_sender.generate_message_2().unwrap();
+        let msg3_sender = msg2_receiver
+            .handle_message_2(&mut msg2_bytes, v_public)
+            .unwrap();
+        let (mut msg3_bytes, u_secret, u_salt) =
+            msg3_sender.generate_message_3().unwrap();
+        let (v_secret, v_salt) = msg3_receiver
+            .handle_message_3(&mut msg3_bytes, u_public)
+            .unwrap();
+
+        assert_eq!(u_secret, v_secret);
+        assert_eq!(u_salt, v_salt);
+    }
+
+    #[test]
+    fn handle_message_1_rejects_malformed_input() {
+        let msg1_receiver = Msg1Receiver::new(V_C_V, V_PRIV);
+        let mut garbage = b"\xff\xff\xff".to_vec();
+
+        assert!(matches!(
+            msg1_receiver.handle_message_1(&mut garbage),
+            Err(OwnOrPeerError::Own(_))
+        ));
+    }
+
+    #[test]
+    fn handle_message_1_rejects_wrong_length_g_x() {
+        let msg1_receiver = Msg1Receiver::new(V_C_V, V_PRIV);
+        // (C_u, G_X), but G_X is one byte short.
+        let mut message_1 =
+            cbor::encode((Bytes::new(U_C_U), Bytes::new(&[0u8; 31])))
+                .unwrap();
+
+        assert!(matches!(
+            msg1_receiver.handle_message_1(&mut message_1),
+            Err(OwnOrPeerError::Own(_))
+        ));
+    }
+
+    #[test]
+    fn handle_message_1_surfaces_a_genuine_peer_error() {
+        let msg1_receiver = Msg1Receiver::new(V_C_V, V_PRIV);
+        let OwnError(mut error_message) =
+            Msg1Sender::new(U_C_U, U_PRIV).abandon("policy check failed");
+
+        assert!(matches!(
+            msg1_receiver.handle_message_1(&mut error_message),
+            Err(OwnOrPeerError::Peer(diagnostic))
+                if diagnostic == "policy check failed"
+        ));
+    }
+
+    #[test]
+    fn handle_message_2_rejects_malformed_input() {
+        let u_public = public_key(&U_PRIV);
+        let (_, msg2_receiver) =
+            Msg1Sender::new(U_C_U, U_PRIV).generate_message_1().unwrap();
+        let mut garbage = b"\xff\xff\xff".to_vec();
+
+        assert!(matches!(
+            msg2_receiver.handle_message_2(&mut garbage, u_public),
+            Err(OwnOrPeerError::Own(_))
+        ));
+    }
+
+    #[test]
+    fn handle_message_2_rejects_signature_verification_failure() {
+        let (mut msg1_bytes, msg2_receiver) =
+            Msg1Sender::new(U_C_U, U_PRIV).generate_message_1().unwrap();
+        let msg2_sender = Msg1Receiver::new(V_C_V, V_PRIV)
+            .handle_message_1(&mut msg1_bytes)
+            .unwrap();
+        let (mut msg2_bytes, _) = msg2_sender.generate_message_2().unwrap();
+
+        // The wrong public key for V.
+        let wrong_public_key = public_key(&U_PRIV);
+
+        assert!(matches!(
+            msg2_receiver.handle_message_2(&mut msg2_bytes, wrong_public_key),
+            Err(OwnOrPeerError::Own(_))
+        ));
+    }
+
+    #[test]
+    fn handle_message_3_rejects_signature_verification_failure() {
+        let v_public = public_key(&V_PRIV);
+        let (mut msg1_bytes, msg2_receiver) =
+            Msg1Sender::new(U_C_U, U_PRIV).generate_message_1().unwrap();
+        let msg2_sender = Msg1Receiver::new(V_C_V, V_PRIV)
+            .handle_message_1(&mut msg1_bytes)
+            .unwrap();
+        let (mut msg2_bytes, msg3_receiver) =
+            msg2_sender.generate_message_2().unwrap();
+        let msg3_sender = msg2_receiver
+            .handle_message_2(&mut msg2_bytes, v_public)
+            .unwrap();
+        let (mut msg3_bytes, _, _) =
+            msg3_sender.generate_message_3().unwrap();
+
+        // The wrong public key for U.
+        let wrong_public_key = public_key(&V_PRIV);
+
+        assert!(matches!(
+            msg3_receiver.handle_message_3(&mut msg3_bytes, wrong_public_key),
+            Err(OwnOrPeerError::Own(_))
+        ));
+    }
+
+    #[test]
+    fn abandon_produces_a_peer_readable_error_message() {
+        let OwnError(error_message) =
+            Msg1Sender::new(U_C_U, U_PRIV).abandon("giving up");
+
+        let (c_x, diagnostic) =
+            super::super::error::parse_error_message(&error_message).unwrap();
+
+        assert_eq!(&c_x[..], b"");
+        assert_eq!(diagnostic, "giving up");
+    }
+}
@@ -0,0 +1,83 @@
+//! EDHOC `error` messages.
+//!
+//! EDHOC defines a dedicated message type for signalling that a party has
+//! abandoned the exchange: it carries the correlation identifier of the
+//! other party (`C_x`) plus a diagnostic text (`ERR_MSG`). Every
+//! `Msg*Sender`/`Msg*Receiver` state can turn itself into one of these
+//! (see e.g. [`Msg1Receiver::abandon`](super::api::Msg1Receiver::abandon)),
+//! consuming itself in the process so the aborted exchange cannot be
+//! driven any further.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde_bytes::{ByteBuf, Bytes};
+
+use crate::{cbor, Result};
+
+/// The CBOR encoded bytes of an EDHOC `error` message we produced
+/// ourselves, ready to be sent to the other party.
+///
+/// Producing one means the handshake is abandoned; there is no way back
+/// into a regular protocol state from here.
+#[derive(Debug, PartialEq)]
+pub struct OwnError(pub Vec<u8>);
+
+/// The outcome of handling a message that turned out to carry an EDHOC
+/// error, distinguishing who raised it.
+#[derive(Debug, PartialEq)]
+pub enum OwnOrPeerError {
+    /// We detected the problem while processing the other party's message.
+    /// This holds the CBOR encoded `error` message to send back.
+    Own(Vec<u8>),
+    /// The other party sent us an `error` message instead of the expected
+    /// handshake message. This holds their diagnostic text (`ERR_MSG`); we
+    /// have nothing left to send in response.
+    Peer(String),
+}
+
+impl From<OwnError> for OwnOrPeerError {
+    fn from(err: OwnError) -> Self {
+        OwnOrPeerError::Own(err.0)
+    }
+}
+
+/// Builds the CBOR encoded EDHOC `error` message.
+///
+/// # Arguments
+/// * `c_x` - The correlation identifier of the other party (`C_x`), as
+///   received in the message whose processing failed. Empty if no
+///   identifier was received yet.
+/// * `err_msg` - A human readable diagnostic string.
+pub fn build_error_message(c_x: &[u8], err_msg: &str) -> Result<OwnError> {
+    // (C_x, ERR_MSG)
+    let message = (Bytes::new(c_x), err_msg);
+
+    Ok(OwnError(cbor::encode(message)?))
+}
+
+/// Tries to parse `bytes` as an EDHOC `error` message, returning the
+/// peer's `C_x` and diagnostic text on success.
+pub fn parse_error_message(bytes: &[u8]) -> Result<(Vec<u8>, String)> {
+    let mut bytes = bytes.to_vec();
+    let message: (ByteBuf, String) = cbor::decode(&mut bytes)?;
+
+    Ok((message.0.into_vec(), message.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_message_roundtrips() {
+        let c_x = b"Party U";
+        let err_msg = "Verification of Message 2 failed";
+
+        let OwnError(bytes) = build_error_message(c_x, err_msg).unwrap();
+        let (decoded_c_x, decoded_err_msg) =
+            parse_error_message(&bytes).unwrap();
+
+        assert_eq!(&c_x[..], &decoded_c_x[..]);
+        assert_eq!(err_msg, decoded_err_msg);
+    }
+}
@@ -0,0 +1,81 @@
+//! Small cryptographic helpers shared by the [`super::api`] state machine.
+
+use alloc::vec::Vec;
+
+use crate::backend::CryptoBackend;
+use crate::{cose, Result};
+
+/// Returns the running transcript hash `TH_i = SHA-256(TH_{i-1} || msg_i)`.
+pub fn transcript_hash(
+    backend: &impl CryptoBackend,
+    prev: &[u8],
+    msg: &[u8],
+) -> Result<Vec<u8>> {
+    let mut input = Vec::with_capacity(prev.len() + msg.len());
+    input.extend_from_slice(prev);
+    input.extend_from_slice(msg);
+
+    Ok(backend.sha256(&input)?.to_vec())
+}
+
+/// Expands a 32-byte seed into the 64-byte (secret || public) Ed25519
+/// keypair encoding [`cose::sign`]/[`cose::verify`] expect for the `EdDsa`
+/// suite.
+///
+/// This lets a single 32-byte value serve as both the ephemeral X25519
+/// private key and the long-term Ed25519 authentication key, which is what
+/// this crate's example does for simplicity.
+pub fn expand_ed25519_keypair(seed: &[u8; 32]) -> Result<[u8; 64]> {
+    let secret = ed25519_dalek::SecretKey::from_bytes(seed)?;
+    let public = ed25519_dalek::PublicKey::from(&secret);
+
+    let mut keypair_bytes = [0; 64];
+    keypair_bytes[..32].copy_from_slice(seed);
+    keypair_bytes[32..].copy_from_slice(public.as_bytes());
+
+    Ok(keypair_bytes)
+}
+
+/// Derives the OSCORE Master Secret and Master Salt from the EDHOC PRK and
+/// the final transcript hash, via HKDF-Expand with the `COSE_KDF_Context`
+/// this crate already builds for OSCORE ([`cose::build_kdf_context`]).
+pub fn derive_oscore_context(
+    backend: &impl CryptoBackend,
+    prk: &[u8],
+    th: &[u8],
+) -> Result<([u8; 16], [u8; 8])> {
+    let secret_info = cose::build_kdf_context("Master Secret", 128, th)?;
+    let salt_info = cose::build_kdf_context("Master Salt", 64, th)?;
+
+    let secret = backend.hkdf_expand(prk, &secret_info, 16)?;
+    let salt = backend.hkdf_expand(prk, &salt_info, 8)?;
+
+    let mut master_secret = [0; 16];
+    let mut master_salt = [0; 8];
+    master_secret.copy_from_slice(&secret);
+    master_salt.copy_from_slice(&salt);
+
+    Ok((master_secret, master_salt))
+}
+
+/// Derives the AEAD key and IV used to protect a `COSE_Encrypt0` envelope
+/// inside an EDHOC message, from the EDHOC PRK and the transcript hash the
+/// caller passes in (`TH_2` for message_2, `TH_3` for message_3).
+pub fn derive_aead_key_iv(
+    backend: &impl CryptoBackend,
+    prk: &[u8],
+    th: &[u8],
+) -> Result<([u8; 16], [u8; 13])> {
+    let key_info = cose::build_kdf_context("AES-CCM-64-64-128", 128, th)?;
+    let iv_info = cose::build_kdf_context("IV-GENERATION", 104, th)?;
+
+    let key = backend.hkdf_expand(prk, &key_info, 16)?;
+    let iv = backend.hkdf_expand(prk, &iv_info, 13)?;
+
+    let mut k = [0; 16];
+    let mut n = [0; 13];
+    k.copy_from_slice(&key);
+    n.copy_from_slice(&iv);
+
+    Ok((k, n))
+}
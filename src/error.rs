@@ -0,0 +1,22 @@
+//! The crate-wide error type.
+
+/// Errors that can occur in this crate.
+#[derive(Debug)]
+pub enum Error {
+    /// A CBOR encoding or decoding operation failed.
+    Cbor,
+    /// A cryptographic operation (signing, verification, key agreement, key
+    /// derivation, or AEAD) failed.
+    Crypto,
+    /// Parsing a DER/PKCS#8 encoded key failed.
+    Asn1,
+}
+
+/// This crate's `Result` alias.
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl From<ed25519_dalek::Error> for Error {
+    fn from(_: ed25519_dalek::Error) -> Self {
+        Error::Crypto
+    }
+}
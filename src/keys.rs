@@ -0,0 +1,300 @@
+//! Import helpers for DER/PKCS#8 encoded authentication keys.
+//!
+//! [`cose::sign`](crate::cose::sign)/[`cose::verify`](crate::cose::verify)
+//! take the raw key material this crate uses directly (a 64-byte Ed25519
+//! keypair, a 32-byte P-256 private scalar or a SEC1 public point). Keys
+//! produced by common PKI/OpenSSL tooling instead come as DER-encoded
+//! `SubjectPublicKeyInfo` (public keys) or PKCS#8 `PrivateKeyInfo`
+//! (private keys). This module implements just enough ASN.1 DER parsing
+//! to recover that raw material, dispatching on the embedded algorithm
+//! OID.
+
+use alloc::vec::Vec;
+
+use crate::cose::SignatureAlg;
+use crate::{Error, Result};
+
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_OID: u8 = 0x06;
+
+/// `1.3.101.112`, id-Ed25519.
+const ED25519_OID: [u8; 3] = [0x2B, 0x65, 0x70];
+/// `1.2.840.10045.2.1`, id-ecPublicKey.
+const EC_PUBLIC_KEY_OID: [u8; 7] =
+    [0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01];
+/// `1.2.840.10045.3.1.7`, the secp256r1 (P-256) named curve.
+const SECP256R1_OID: [u8; 8] =
+    [0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
+
+/// Returns the algorithm and raw public key material recovered from a
+/// DER-encoded `SubjectPublicKeyInfo`.
+///
+/// For `EdDsa` this is the 32-byte Ed25519 public key; for `Es256` it's
+/// the uncompressed SEC1 point `cose::verify` already accepts.
+pub fn parse_public_key(der: &[u8]) -> Result<(SignatureAlg, Vec<u8>)> {
+    let mut reader = Reader::new(der);
+    let spki = reader.read_tlv(TAG_SEQUENCE)?;
+
+    let mut spki = Reader::new(spki);
+    let algorithm = spki.read_tlv(TAG_SEQUENCE)?;
+    let subject_public_key = spki.read_tlv(TAG_BIT_STRING)?;
+
+    let alg = parse_algorithm(algorithm)?;
+    let key = bit_string_bytes(subject_public_key)?;
+
+    Ok((alg, key.to_vec()))
+}
+
+/// Returns the algorithm and raw private key material recovered from a
+/// PKCS#8 `PrivateKeyInfo`.
+///
+/// For `EdDsa` this is the 64-byte (secret || public) Ed25519 keypair
+/// `cose::sign` expects; for `Es256` it's the 32-byte private scalar.
+pub fn parse_private_key(der: &[u8]) -> Result<(SignatureAlg, Vec<u8>)> {
+    let mut reader = Reader::new(der);
+    let pki = reader.read_tlv(TAG_SEQUENCE)?;
+
+    let mut pki = Reader::new(pki);
+    let _version = pki.read_tlv(TAG_INTEGER)?;
+    let algorithm = pki.read_tlv(TAG_SEQUENCE)?;
+    let private_key = pki.read_tlv(TAG_OCTET_STRING)?;
+
+    match parse_algorithm(algorithm)? {
+        SignatureAlg::EdDsa => {
+            // The PKCS#8 `privateKey` OCTET STRING wraps another OCTET
+            // STRING holding the raw 32-byte seed (RFC 8410).
+            let seed = Reader::new(private_key).read_tlv(TAG_OCTET_STRING)?;
+            if seed.len() != 32 {
+                return Err(Error::Asn1);
+            }
+
+            let secret = ed25519_dalek::SecretKey::from_bytes(seed)
+                .map_err(|_| Error::Crypto)?;
+            let public = ed25519_dalek::PublicKey::from(&secret);
+
+            let mut keypair = Vec::with_capacity(64);
+            keypair.extend_from_slice(seed);
+            keypair.extend_from_slice(public.as_bytes());
+
+            Ok((SignatureAlg::EdDsa, keypair))
+        }
+        SignatureAlg::Es256 => {
+            // `privateKey` wraps an RFC 5915 `ECPrivateKey` SEQUENCE;
+            // we only need its `privateKey` OCTET STRING.
+            let ec_private_key =
+                Reader::new(private_key).read_tlv(TAG_SEQUENCE)?;
+            let mut ec_private_key = Reader::new(ec_private_key);
+            let _version = ec_private_key.read_tlv(TAG_INTEGER)?;
+            let scalar = ec_private_key.read_tlv(TAG_OCTET_STRING)?;
+            if scalar.len() != 32 {
+                return Err(Error::Asn1);
+            }
+
+            Ok((SignatureAlg::Es256, scalar.to_vec()))
+        }
+    }
+}
+
+/// Parses an `AlgorithmIdentifier` `SEQUENCE`, returning the
+/// [`SignatureAlg`] it identifies.
+fn parse_algorithm(algorithm: &[u8]) -> Result<SignatureAlg> {
+    let mut algorithm = Reader::new(algorithm);
+    let oid = algorithm.read_tlv(TAG_OID)?;
+
+    if oid == ED25519_OID {
+        return Ok(SignatureAlg::EdDsa);
+    }
+    if oid == EC_PUBLIC_KEY_OID {
+        let curve = algorithm.read_tlv(TAG_OID)?;
+        return if curve == SECP256R1_OID {
+            Ok(SignatureAlg::Es256)
+        } else {
+            Err(Error::Asn1)
+        };
+    }
+
+    Err(Error::Asn1)
+}
+
+/// Strips the "unused bits" octet off a `BIT STRING` value, rejecting
+/// anything but a whole number of bytes (which is all a raw key is).
+fn bit_string_bytes(bit_string: &[u8]) -> Result<&[u8]> {
+    match bit_string.split_first() {
+        Some((0, key)) => Ok(key),
+        _ => Err(Error::Asn1),
+    }
+}
+
+/// A minimal ASN.1 DER tag-length-value reader, supporting only the tags
+/// this module needs (`SEQUENCE`, `OBJECT IDENTIFIER`, `BIT STRING`,
+/// `OCTET STRING`, `INTEGER`) and definite-form lengths.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes }
+    }
+
+    /// Reads the next TLV, requiring it to have the given `tag`, and
+    /// advances past it.
+    fn read_tlv(&mut self, tag: u8) -> Result<&'a [u8]> {
+        let (got_tag, header_len, len) = self.read_header()?;
+        if got_tag != tag {
+            return Err(Error::Asn1);
+        }
+
+        let end = header_len.checked_add(len).ok_or(Error::Asn1)?;
+        let value = self.bytes.get(header_len..end).ok_or(Error::Asn1)?;
+        self.bytes = &self.bytes[end..];
+
+        Ok(value)
+    }
+
+    /// Parses the tag and length octets at the start of `self.bytes`,
+    /// returning `(tag, header_len, value_len)` without consuming
+    /// anything.
+    fn read_header(&self) -> Result<(u8, usize, usize)> {
+        let &tag = self.bytes.first().ok_or(Error::Asn1)?;
+        let &first_len = self.bytes.get(1).ok_or(Error::Asn1)?;
+
+        if first_len & 0x80 == 0 {
+            return Ok((tag, 2, first_len as usize));
+        }
+
+        // Long form: the lower 7 bits give the number of subsequent
+        // length octets.
+        let n = (first_len & 0x7F) as usize;
+        if n == 0 || n > core::mem::size_of::<usize>() {
+            return Err(Error::Asn1);
+        }
+        let len_bytes = self.bytes.get(2..2 + n).ok_or(Error::Asn1)?;
+        let len = len_bytes
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 8) | b as usize);
+
+        Ok((tag, 2 + n, len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    /// Wraps `value` in a DER TLV with the given `tag`.
+    fn tlv(tag: u8, value: &[u8]) -> Vec<u8> {
+        assert!(value.len() < 128, "test helper only needs short form");
+
+        let mut out = vec![tag, value.len() as u8];
+        out.extend_from_slice(value);
+
+        out
+    }
+
+    fn ed25519_spki(public_key: &[u8; 32]) -> Vec<u8> {
+        let algorithm = tlv(TAG_OID, &ED25519_OID);
+        let algorithm = tlv(TAG_SEQUENCE, &algorithm);
+        let mut bit_string_value = vec![0x00];
+        bit_string_value.extend_from_slice(public_key);
+        let bit_string = tlv(TAG_BIT_STRING, &bit_string_value);
+
+        let mut content = algorithm;
+        content.extend_from_slice(&bit_string);
+
+        tlv(TAG_SEQUENCE, &content)
+    }
+
+    fn ed25519_pkcs8(seed: &[u8; 32]) -> Vec<u8> {
+        let version = tlv(TAG_INTEGER, &[0x00]);
+        let algorithm = tlv(TAG_OID, &ED25519_OID);
+        let algorithm = tlv(TAG_SEQUENCE, &algorithm);
+        let private_key = tlv(TAG_OCTET_STRING, seed);
+        let private_key = tlv(TAG_OCTET_STRING, &private_key);
+
+        let mut content = version;
+        content.extend_from_slice(&algorithm);
+        content.extend_from_slice(&private_key);
+
+        tlv(TAG_SEQUENCE, &content)
+    }
+
+    fn ec2_spki(point: &[u8; 65]) -> Vec<u8> {
+        let algorithm_oid = tlv(TAG_OID, &EC_PUBLIC_KEY_OID);
+        let curve_oid = tlv(TAG_OID, &SECP256R1_OID);
+        let mut algorithm = algorithm_oid;
+        algorithm.extend_from_slice(&curve_oid);
+        let algorithm = tlv(TAG_SEQUENCE, &algorithm);
+
+        let mut bit_string_value = vec![0x00];
+        bit_string_value.extend_from_slice(point);
+        let bit_string = tlv(TAG_BIT_STRING, &bit_string_value);
+
+        let mut content = algorithm;
+        content.extend_from_slice(&bit_string);
+
+        tlv(TAG_SEQUENCE, &content)
+    }
+
+    #[test]
+    fn ed25519_public_key_roundtrips() {
+        let public_key = [0xAA; 32];
+        let der = ed25519_spki(&public_key);
+
+        let (alg, key) = parse_public_key(&der).unwrap();
+
+        assert_eq!(alg, SignatureAlg::EdDsa);
+        assert_eq!(&key[..], &public_key[..]);
+    }
+
+    #[test]
+    fn ed25519_private_key_roundtrips() {
+        let seed = [0x11; 32];
+        let der = ed25519_pkcs8(&seed);
+
+        let (alg, keypair) = parse_private_key(&der).unwrap();
+
+        assert_eq!(alg, SignatureAlg::EdDsa);
+        assert_eq!(keypair.len(), 64);
+        assert_eq!(&keypair[..32], &seed[..]);
+    }
+
+    #[test]
+    fn ec2_public_key_roundtrips() {
+        let mut point = [0; 65];
+        point[0] = 0x04;
+        for (i, b) in point[1..].iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let der = ec2_spki(&point);
+
+        let (alg, key) = parse_public_key(&der).unwrap();
+
+        assert_eq!(alg, SignatureAlg::Es256);
+        assert_eq!(&key[..], &point[..]);
+    }
+
+    #[test]
+    fn unknown_oid_rejected() {
+        let algorithm = tlv(TAG_OID, &[0x01, 0x02, 0x03]);
+        let algorithm = tlv(TAG_SEQUENCE, &algorithm);
+        let bit_string = tlv(TAG_BIT_STRING, &[0x00, 0xAA]);
+        let mut content = algorithm;
+        content.extend_from_slice(&bit_string);
+        let der = tlv(TAG_SEQUENCE, &content);
+
+        assert!(parse_public_key(&der).is_err());
+    }
+
+    #[test]
+    fn truncated_input_rejected() {
+        let public_key = [0xAA; 32];
+        let der = ed25519_spki(&public_key);
+
+        assert!(parse_public_key(&der[..der.len() - 5]).is_err());
+    }
+}